@@ -1,8 +1,11 @@
 use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
 
-use statrs::distribution::{Binomial, Discrete, DiscreteCDF};
+use statrs::distribution::{Beta, Binomial, ContinuousCDF, Discrete, DiscreteCDF};
+use statrs::function::gamma::ln_gamma;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Alternative {
     TwoSided,
@@ -10,6 +13,34 @@ pub enum Alternative {
     Greater,
 }
 
+/// Error returned when the inputs to a binomial test are invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinomialError {
+    /// The number of trials `n` was zero.
+    NIsZero,
+    /// The number of successes `k` exceeded the number of trials `n`.
+    KExceedsN { k: u64, n: u64 },
+    /// The hypothesized probability `p` was outside the interval [0, 1].
+    POutOfRange { p: f64 },
+    /// The hypothesized probability `p` was NaN.
+    PNotFinite,
+}
+
+impl fmt::Display for BinomialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinomialError::NIsZero => write!(f, "Number of trials n must be > 0"),
+            BinomialError::KExceedsN { k, n } => {
+                write!(f, "Number of successes k={k} must be <= n={n}")
+            }
+            BinomialError::POutOfRange { p } => write!(f, "Probability p={p} must be in [0, 1]"),
+            BinomialError::PNotFinite => write!(f, "Probability p must not be NaN"),
+        }
+    }
+}
+
+impl Error for BinomialError {}
+
 /// Performs a binomial test for a given number of successes, trials, and hypothesized probability.
 ///
 /// # Arguments
@@ -21,14 +52,15 @@ pub enum Alternative {
 ///
 /// # Returns
 ///
-/// A `Result` containing the p-value (`f64`) if successful, or a `String` with an error message if the inputs are invalid.
+/// A `Result` containing the p-value (`f64`) if successful, or a [`BinomialError`] if the inputs are invalid.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - `p` is not in the interval [0.0, 1.0].
-/// - `k > n`.
-/// - `n < 1`
+/// - `p` is NaN ([`BinomialError::PNotFinite`]).
+/// - `p` is not in the interval [0.0, 1.0] ([`BinomialError::POutOfRange`]).
+/// - `k > n` ([`BinomialError::KExceedsN`]).
+/// - `n < 1` ([`BinomialError::NIsZero`]).
 ///
 /// # Example
 ///
@@ -38,15 +70,18 @@ pub enum Alternative {
 /// let result = binomial_test(5, 10, 0.5, Alternative::TwoSided);
 /// assert!(result.is_ok());
 /// ```
-pub fn binomial_test(k: u64, n: u64, p: f64, alt: Alternative) -> Result<f64, String> {
+pub fn binomial_test(k: u64, n: u64, p: f64, alt: Alternative) -> Result<f64, BinomialError> {
     if n < 1 {
-        return Err("Number of trials n must be > 0".to_string());
+        return Err(BinomialError::NIsZero);
     }
     if k > n {
-        return Err("Number of successes k must be <= n and > 0".to_string());
+        return Err(BinomialError::KExceedsN { k, n });
+    }
+    if p.is_nan() {
+        return Err(BinomialError::PNotFinite);
     }
     if !(0. ..=1.).contains(&p) {
-        return Err("Probability p must be in [0, 1]".to_string());
+        return Err(BinomialError::POutOfRange { p });
     }
     let binom = Binomial::new(p, n).expect("Invalid binomial parameters");
 
@@ -98,6 +133,313 @@ pub fn binomial_test(k: u64, n: u64, p: f64, alt: Alternative) -> Result<f64, St
     }
 }
 
+/// Outcome of a binomial test, bundling the observed counts with the p-value
+/// and an estimate of the success proportion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinomTestResult {
+    /// Number of observed successes.
+    pub k: u64,
+    /// Total number of trials.
+    pub n: u64,
+    /// Point estimate of the success probability, `k / n`.
+    pub proportion_estimate: f64,
+    /// The test's p-value.
+    pub pvalue: f64,
+    /// The alternative hypothesis the test was run against.
+    pub alt: Alternative,
+}
+
+impl BinomTestResult {
+    /// Computes the exact Clopper–Pearson confidence interval for the success
+    /// proportion at the given `confidence_level` (e.g. `0.95`).
+    ///
+    /// The interval is obtained by inverting the Beta distribution: for a
+    /// two-sided level `1 - α` the lower bound is the `α/2` quantile of
+    /// `Beta(k, n - k + 1)` and the upper bound the `1 - α/2` quantile of
+    /// `Beta(k + 1, n - k)`, with `lower = 0` when `k = 0` and `upper = 1`
+    /// when `k = n`. For a one-sided [`Alternative`] only the bound on the
+    /// tested side is estimated and the other side is clamped to 0 or 1.
+    pub fn proportion_ci(&self, confidence_level: f64) -> (f64, f64) {
+        match self.alt {
+            Alternative::TwoSided => {
+                let alpha = 1.0 - confidence_level;
+                let lower = self.lower_bound(alpha / 2.0);
+                let upper = self.upper_bound(1.0 - alpha / 2.0);
+                (lower, upper)
+            }
+            Alternative::Greater => {
+                let alpha = 1.0 - confidence_level;
+                (self.lower_bound(alpha), 1.0)
+            }
+            Alternative::Less => {
+                let alpha = 1.0 - confidence_level;
+                (0.0, self.upper_bound(1.0 - alpha))
+            }
+        }
+    }
+
+    fn lower_bound(&self, q: f64) -> f64 {
+        if self.k == 0 {
+            0.0
+        } else {
+            Beta::new(self.k as f64, (self.n - self.k + 1) as f64)
+                .expect("Invalid beta parameters")
+                .inverse_cdf(q)
+        }
+    }
+
+    fn upper_bound(&self, q: f64) -> f64 {
+        if self.k == self.n {
+            1.0
+        } else {
+            Beta::new((self.k + 1) as f64, (self.n - self.k) as f64)
+                .expect("Invalid beta parameters")
+                .inverse_cdf(q)
+        }
+    }
+}
+
+/// Performs a binomial test and returns a [`BinomTestResult`] bundling the
+/// p-value with the observed counts and the proportion estimate.
+///
+/// This is a thin wrapper over [`binomial_test`]; see it for the meaning of
+/// the arguments and the error conditions.
+///
+/// # Errors
+///
+/// Propagates any [`BinomialError`] produced by [`binomial_test`].
+pub fn binom_test(
+    k: u64,
+    n: u64,
+    p: f64,
+    alt: Alternative,
+) -> Result<BinomTestResult, BinomialError> {
+    let pvalue = binomial_test(k, n, p, alt)?;
+    Ok(BinomTestResult {
+        k,
+        n,
+        proportion_estimate: k as f64 / n as f64,
+        pvalue,
+        alt,
+    })
+}
+
+/// Draws a single sample from `Binomial(n, p)`.
+///
+/// With `q = min(p, 1 - p)`, the standard CDF inversion is used when
+/// `n·q <= 30`; for larger `n·q` the routine falls back to a normal-approximation acceptance–rejection path so
+/// that the expected number of iterations stays bounded.
+#[cfg(feature = "rand")]
+pub fn sample(n: u64, p: f64, rng: &mut impl rand::Rng) -> u64 {
+    let q = p.min(1.0 - p);
+    let x = if (n as f64) * q <= 30.0 {
+        sample_inversion(n, q, rng)
+    } else {
+        sample_normal_rejection(n, q, rng)
+    };
+    if p > 0.5 {
+        n - x
+    } else {
+        x
+    }
+}
+
+#[cfg(feature = "rand")]
+fn sample_inversion(n: u64, q: f64, rng: &mut impl rand::Rng) -> u64 {
+    let u: f64 = rng.gen();
+    let ratio = q / (1.0 - q);
+    let mut s = (1.0 - q).powi(n as i32);
+    let mut f = s;
+    let mut x = 0u64;
+    while u > f && x < n {
+        x += 1;
+        s *= ((n - x + 1) as f64 / x as f64) * ratio;
+        f += s;
+    }
+    x
+}
+
+#[cfg(feature = "rand")]
+fn sample_normal_rejection(n: u64, q: f64, rng: &mut impl rand::Rng) -> u64 {
+    let binom = Binomial::new(q, n).expect("Invalid binomial parameters");
+    let mu = n as f64 * q;
+    let sigma = (mu * (1.0 - q)).sqrt();
+    // Generous envelope constant; it only affects the expected loop count, not
+    // correctness, as long as `m * g(y) >= pmf(y)` for every integer `y`.
+    let m = 3.0;
+    loop {
+        // Box–Muller transform to obtain a standard normal deviate.
+        let u1: f64 = rng.gen();
+        let u2: f64 = rng.gen();
+        let z = (-2.0 * u1.max(f64::MIN_POSITIVE).ln()).sqrt()
+            * (2.0 * std::f64::consts::PI * u2).cos();
+        let y = (mu + sigma * z).round();
+        if y < 0.0 || y > n as f64 {
+            continue;
+        }
+        let y = y as u64;
+        let g = (-0.5 * z * z).exp() / (sigma * (2.0 * std::f64::consts::PI).sqrt());
+        let accept: f64 = rng.gen();
+        if accept * m * g <= binom.pmf(y) {
+            return y;
+        }
+    }
+}
+
+/// Estimates the statistical power of a two-sided or one-sided
+/// [`binomial_test`] by Monte-Carlo simulation.
+///
+/// Draws `iterations` samples of size `n` under the true success probability
+/// `p_true`, tests each against the null `p_null`, and returns the fraction of
+/// trials whose p-value falls below `alpha`.
+#[cfg(feature = "rand")]
+pub fn power(
+    n: u64,
+    p_null: f64,
+    p_true: f64,
+    alt: Alternative,
+    alpha: f64,
+    iterations: u64,
+    rng: &mut impl rand::Rng,
+) -> f64 {
+    let mut rejections = 0u64;
+    for _ in 0..iterations {
+        let k = sample(n, p_true, rng);
+        if let Ok(pvalue) = binomial_test(k, n, p_null, alt) {
+            if pvalue < alpha {
+                rejections += 1;
+            }
+        }
+    }
+    rejections as f64 / iterations as f64
+}
+
+/// Natural logarithm of the binomial coefficient `C(n, k)` for real `n, k`,
+/// computed through the log-gamma function for numerical stability.
+fn ln_binom(n: f64, k: f64) -> f64 {
+    ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0)
+}
+
+/// Performs a negative-binomial test for observing `k` failures before the
+/// `r`-th success under `NBin(r, p)`.
+///
+/// This suits sequential designs that stop after a fixed number of successes
+/// rather than a fixed number of trials. The two-sided p-value uses the same
+/// "sum of pmf values no larger than the observed one" construction as
+/// [`binomial_test`].
+///
+/// # Errors
+///
+/// Returns [`BinomialError::PNotFinite`] if `p` is NaN and
+/// [`BinomialError::POutOfRange`] if `p` is not in `[0, 1]`.
+pub fn neg_binomial_test(k: u64, r: f64, p: f64, alt: Alternative) -> Result<f64, BinomialError> {
+    if p.is_nan() {
+        return Err(BinomialError::PNotFinite);
+    }
+    if !(0. ..=1.).contains(&p) {
+        return Err(BinomialError::POutOfRange { p });
+    }
+
+    let pmf = |x: u64| {
+        (ln_binom(x as f64 + r - 1.0, x as f64) + r * p.ln() + (x as f64) * (1.0 - p).ln()).exp()
+    };
+
+    // The support is unbounded, so extend the summation until the upper tail
+    // is numerically negligible (and always at least past the observation).
+    // A hard cap keeps both the extension and the two-sided sum bounded for
+    // degenerate (`p = 0`, where `mean` is infinite) or extreme-mean inputs.
+    const MAX_SUPPORT: u64 = 1_000_000;
+    let mean = r * (1.0 - p) / p;
+    let start = if mean.is_finite() {
+        (mean as u64).min(MAX_SUPPORT).max(k)
+    } else {
+        MAX_SUPPORT.max(k)
+    };
+    let cap = start.saturating_add(MAX_SUPPORT);
+    let mut hi = start.saturating_add(1);
+    while hi < cap && pmf(hi) > 1e-15 {
+        hi = hi.saturating_add(1);
+    }
+
+    let cdf = |x: u64| (0..=x).map(pmf).sum::<f64>();
+
+    match alt {
+        Alternative::Less => Ok(cdf(k)),
+
+        // `Greater` is `P(X >= k)`; the lower edge is exactly 1.
+        Alternative::Greater => {
+            if k == 0 {
+                Ok(1.0)
+            } else {
+                Ok((k..=hi).map(pmf).sum::<f64>())
+            }
+        }
+
+        // Two-sided p-value: the total mass of all outcomes at least as
+        // unlikely as the observed one, summed directly over the truncated
+        // support `0..=hi` instead of pivoting on the mean.
+        Alternative::TwoSided => {
+            let d = pmf(k) * (1.0 + 1e-7);
+            Ok((0..=hi).map(pmf).filter(|&px| px <= d).sum::<f64>())
+        }
+    }
+}
+
+/// Natural logarithm of the Beta function `B(x, y)`, expressed through
+/// log-gamma for numerical stability.
+fn ln_beta(x: f64, y: f64) -> f64 {
+    ln_gamma(x) + ln_gamma(y) - ln_gamma(x + y)
+}
+
+/// Performs a beta-binomial test of `k` successes in `n` trials under the
+/// null `BetaBinomial(n, a, b)`.
+///
+/// The beta-binomial mixes the success probability over a `Beta(a, b)` prior,
+/// which accommodates counts that are overdispersed relative to the binomial.
+/// The two-sided p-value is the total probability of all outcomes whose pmf
+/// does not exceed that of the observed `k`, summed over the whole support
+/// rather than found by the tail search that [`binomial_test`] uses.
+///
+/// # Errors
+///
+/// Returns [`BinomialError::NIsZero`] if `n < 1` and
+/// [`BinomialError::KExceedsN`] if `k > n`.
+pub fn beta_binomial_test(
+    k: u64,
+    n: u64,
+    a: f64,
+    b: f64,
+    alt: Alternative,
+) -> Result<f64, BinomialError> {
+    if n < 1 {
+        return Err(BinomialError::NIsZero);
+    }
+    if k > n {
+        return Err(BinomialError::KExceedsN { k, n });
+    }
+
+    let ln_beta_ab = ln_beta(a, b);
+    let pmf = |x: u64| {
+        (ln_binom(n as f64, x as f64) + ln_beta(x as f64 + a, (n - x) as f64 + b) - ln_beta_ab)
+            .exp()
+    };
+
+    match alt {
+        Alternative::Less => Ok((0..=k).map(pmf).sum::<f64>()),
+
+        Alternative::Greater => Ok((k..=n).map(pmf).sum::<f64>()),
+
+        // Two-sided p-value: the total mass of all outcomes at least as
+        // unlikely as the observed one. The beta-binomial is U-shaped for
+        // `a, b < 1`, so we sum over the whole support instead of pivoting on
+        // the mean as the binomial does.
+        Alternative::TwoSided => {
+            let d = pmf(k) * (1.0 + 1e-7);
+            Ok((0..=n).map(pmf).filter(|&px| px <= d).sum::<f64>())
+        }
+    }
+}
+
 fn binary_search(f: &dyn Fn(u64) -> f64, key: f64, mut low: u64, mut high: u64) -> u64 {
     while low < high {
         let mid = low + (high - low) / 2;
@@ -190,4 +532,114 @@ mod tests {
             5.29655579272766e-63
         ));
     }
+
+    #[test]
+    fn test_neg_binomial_test() {
+        // NBin(r = 2, p = 0.7) over failures k: pmf(k) = (k + 1) * 0.49 * 0.3^k.
+        assert!(approx_eq(
+            neg_binomial_test(1, 2.0, 0.7, Alternative::Less).unwrap(),
+            0.784
+        ));
+        // P(X >= 0) is exactly 1.
+        assert_eq!(
+            neg_binomial_test(0, 2.0, 0.7, Alternative::Greater).unwrap(),
+            1.0
+        );
+        // Two-sided at k = 2 excludes the two more-likely outcomes k = 0, 1.
+        assert!(approx_eq(
+            neg_binomial_test(2, 2.0, 0.7, Alternative::TwoSided).unwrap(),
+            0.216
+        ));
+        // k = 0 is the mode, so every outcome is at least as unlikely.
+        assert!(approx_eq(
+            neg_binomial_test(0, 2.0, 0.7, Alternative::TwoSided).unwrap(),
+            1.0
+        ));
+    }
+
+    #[test]
+    fn test_beta_binomial_test() {
+        // BetaBinomial(n = 10, a = b = 2): pmf(k) = (k + 1) * (11 - k) / 286,
+        // symmetric about the mode at k = 5.
+        assert!(approx_eq(
+            beta_binomial_test(3, 10, 2.0, 2.0, Alternative::Less).unwrap(),
+            90.0 / 286.0
+        ));
+        assert!(approx_eq(
+            beta_binomial_test(3, 10, 2.0, 2.0, Alternative::Greater).unwrap(),
+            228.0 / 286.0
+        ));
+        // Two-sided excludes only the more-likely central outcome k = 5.
+        assert!(approx_eq(
+            beta_binomial_test(4, 10, 2.0, 2.0, Alternative::TwoSided).unwrap(),
+            250.0 / 286.0
+        ));
+        // Symmetry: f(k) = f(n - k).
+        assert!(approx_eq(
+            beta_binomial_test(6, 10, 2.0, 2.0, Alternative::TwoSided).unwrap(),
+            250.0 / 286.0
+        ));
+        // The mode itself yields the full mass.
+        assert!(approx_eq(
+            beta_binomial_test(5, 10, 2.0, 2.0, Alternative::TwoSided).unwrap(),
+            1.0
+        ));
+    }
+
+    #[test]
+    fn test_binom_test_result() {
+        let result = binom_test(5, 10, 0.5, Alternative::TwoSided).unwrap();
+        assert!(approx_eq(result.proportion_estimate, 0.5));
+        assert!(approx_eq(result.pvalue, 1.0));
+
+        // Exact Clopper–Pearson interval for 5/10 at 95%, matching SciPy's
+        // binomtest(5, 10).proportion_ci() = (0.187086, 0.812914).
+        let (lo, hi) = result.proportion_ci(0.95);
+        assert!((lo - 0.187086).abs() < 1e-4);
+        assert!((hi - 0.812914).abs() < 1e-4);
+
+        // k = 0 clamps the lower bound to 0; upper is the 0.975 Beta quantile.
+        let (lo, hi) = binom_test(0, 10, 0.5, Alternative::TwoSided)
+            .unwrap()
+            .proportion_ci(0.95);
+        assert_eq!(lo, 0.0);
+        assert!((hi - 0.308537).abs() < 1e-4);
+
+        // k = n clamps the upper bound to 1.
+        let (lo, hi) = binom_test(10, 10, 0.5, Alternative::TwoSided)
+            .unwrap()
+            .proportion_ci(0.95);
+        assert!((lo - 0.691463).abs() < 1e-4);
+        assert_eq!(hi, 1.0);
+
+        // One-sided intervals leave the untested side clamped.
+        let (_, hi) = binom_test(5, 10, 0.5, Alternative::Greater)
+            .unwrap()
+            .proportion_ci(0.95);
+        assert_eq!(hi, 1.0);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sample_and_power() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(42);
+
+        // Degenerate probabilities are deterministic.
+        assert_eq!(sample(10, 0.0, &mut rng), 0);
+        assert_eq!(sample(10, 1.0, &mut rng), 10);
+
+        // Ordinary draws stay within the support.
+        for _ in 0..1000 {
+            assert!(sample(25, 0.4, &mut rng) <= 25);
+        }
+
+        // A large effect size against the null gives high power.
+        let pw = power(10, 0.5, 0.95, Alternative::TwoSided, 0.05, 1000, &mut rng);
+        assert!(pw > 0.8);
+        // Power is always a valid proportion.
+        assert!((0.0..=1.0).contains(&pw));
+    }
 }